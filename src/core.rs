@@ -4,12 +4,14 @@ mod timecode_parts;
 
 mod time;
 mod timebase;
+mod frames;
 
 mod data_box;
 mod metadata_support;
 
 pub use data_box::*;
 pub use data_box::*;
+pub use frames::*;
 pub use metadata_support::*;
 pub use time::*;
 pub use timebase::*;
@@ -3,6 +3,7 @@
 use std::any::Any;
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
 
@@ -37,17 +38,9 @@ let got  = data_box.get::<i32>("key2");
 assert_eq!(got,None);
 ```
 */
-#[derive(Debug,Clone)]
+#[derive(Debug,Clone,Default)]
 pub struct DataBox {
-    data_ref: Box<HashMap<String, Arc<dyn Any + Send + Sync>>>,
-}
-
-impl Default for DataBox {
-    fn default() -> Self {
-        Self {
-            data_ref: Box::new(HashMap::new()),
-        }
-    }
+    data_ref: HashMap<String, Arc<dyn Any + Send + Sync>>,
 }
 
 impl DataBox {
@@ -73,6 +66,87 @@ impl DataBox {
         self.data_ref.insert(String::from(key), Arc::new(value));
     }
 
+    /**
+    取出数据：删除键并返回保存的值。
+    如果值的类型不符，则不做任何修改并返回 `None`。
+    -----
+    Remove the key and return the stored value.
+    If the type doesn't match, nothing is removed and `None` is returned.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::DataBox;
+    let mut data_box = DataBox::default();
+    data_box.set("name", String::from("clip"));
+
+    assert_eq!(data_box.take::<i32>("name"), None);
+    assert_eq!(data_box.take::<String>("name"), Some(String::from("clip")));
+    assert_eq!(data_box.get::<String>("name"), None);
+    ```
+    */
+    pub fn take<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: Any + Sync + Send + Clone,
+    {
+        if !self.data_ref.get(key)?.is::<T>() {
+            return None;
+        }
+        self.data_ref
+            .remove(key)
+            .and_then(|any| any.downcast::<T>().ok())
+            .map(Arc::unwrap_or_clone)
+    }
+
+    ///通过带有类型的键获取数据。
+    pub fn get_typed<T>(&self, key: MetaKey<T>) -> Option<T>
+    where
+        T: Any + Sync + Send + Clone,
+    {
+        self.get(key.key())
+    }
+
+    ///通过带有类型的键保存数据。
+    pub fn set_typed<T>(&mut self, key: MetaKey<T>, value: T)
+    where
+        T: Any + Sync + Send + Clone,
+    {
+        self.set(key.key(), value)
+    }
+
+    /**
+    按照类型 `T` 比较两个 DataBox 在指定键上的值是否都相等。
+
+    由于 `dyn Any` 无法直接比较，只能由调用者指定键和类型。
+    两边都没有某个键（或者类型都不符）时，视为在这个键上相等。
+    -----
+    Compare the values of the given keys as type `T`.
+    A key missing (or of another type) on both sides counts as equal.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::DataBox;
+    let mut a = DataBox::default();
+    a.set("name", String::from("clip"));
+    a.set("reel", String::from("A001"));
+    let mut b = a.clone();
+    assert!(a.eq_typed::<String>(&b, &["name", "reel"]));
+
+    b.set("reel", String::from("A002"));
+    assert!(a.eq_typed::<String>(&b, &["name"]));
+    assert!(!a.eq_typed::<String>(&b, &["name", "reel"]));
+    ```
+    */
+    pub fn eq_typed<T>(&self, other: &DataBox, keys: &[&str]) -> bool
+    where
+        T: Any + Sync + Send + Clone + PartialEq,
+    {
+        keys.iter().all(|key| {
+            let mine = self.data_ref.get(*key).and_then(|any| any.downcast_ref::<T>());
+            let theirs = other.data_ref.get(*key).and_then(|any| any.downcast_ref::<T>());
+            mine == theirs
+        })
+    }
+
     pub fn erase(&mut self, key: &str) {
         self.data_ref.remove(key);
     }
@@ -94,3 +168,49 @@ where
         result
     }
 }
+
+/**
+MetaKey 是一个带有类型的键，把字符串键和值的类型绑定在一起。
+
+字符串键写错或者类型写错时，`DataBox::get()` 只会默默地返回 `None`。
+而使用 MetaKey 时，键和类型只需要定义一次，类型不符的调用会在编译期就被发现。
+-----
+MetaKey is a typed key which binds a string key to the type of its value,
+so type mismatches are caught at the call site.
+
+Example:
+```rust
+# use rusty_studio::core::{DataBox, MetaKey};
+const ENABLED: MetaKey<bool> = MetaKey::new("enabled");
+const LABEL: MetaKey<String> = MetaKey::new("label");
+
+let mut data_box = DataBox::default();
+data_box.set_typed(ENABLED, true);
+data_box.set_typed(LABEL, String::from("dialogue"));
+
+assert_eq!(data_box.get_typed(ENABLED), Some(true));
+assert_eq!(data_box.get_typed(LABEL), Some(String::from("dialogue")));
+assert_eq!(data_box.get::<bool>(LABEL.key()), None);
+```
+*/
+#[derive(Debug)]
+pub struct MetaKey<T>(&'static str, PhantomData<T>);
+
+impl<T> MetaKey<T> {
+    pub const fn new(key: &'static str) -> Self {
+        MetaKey(key, PhantomData)
+    }
+
+    ///返回字符串形式的键。
+    pub fn key(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl<T> Clone for MetaKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MetaKey<T> {}
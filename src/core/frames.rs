@@ -0,0 +1,78 @@
+use super::time::Time;
+use super::timebase::Timebase;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/**
+Frames 表示一个帧数。
+
+帧数和毫秒数在程序中都是整数，很容易混用而导致错误。
+使用 Frames 可以在编译期就把这两种单位区分开来，二者之间只能通过 `Timebase` 互相转换。
+-----
+Frames represents a number of frames.
+It keeps frame counts and millisecond `Time` apart at compile time,
+they can only be converted to each other through a `Timebase`.
+
+Example:
+```rust
+# use rusty_studio::core::{Frames, Time, Timebase};
+let timebase = Timebase::new(24);
+let time = Frames(48).to_time(&timebase);
+assert_eq!(time.to_millisecond(), 2000);
+assert_eq!(Frames::from_time(&time, &timebase), Frames(48));
+
+assert_eq!(Frames(48) + Frames(2), Frames(50));
+assert_eq!(Frames(48) - Frames(50), Frames(-2));
+```
+*/
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Frames(pub i64);
+
+impl Frames {
+    ///按照时基换算为时间，相当于 `Time::from_total_frames()`。
+    pub fn to_time(&self, timebase: &Timebase) -> Time {
+        Time::from_total_frames(self.0, timebase)
+    }
+
+    ///按照时基从时间换算帧数，相当于 `Time::to_total_frames()`。
+    pub fn from_time(time: &Time, timebase: &Timebase) -> Self {
+        Frames(time.to_total_frames(timebase))
+    }
+}
+
+impl From<i64> for Frames {
+    fn from(frames: i64) -> Self {
+        Frames(frames)
+    }
+}
+
+impl From<Frames> for i64 {
+    fn from(frames: Frames) -> Self {
+        frames.0
+    }
+}
+
+impl Add<Frames> for Frames {
+    type Output = Frames;
+    fn add(self, other: Frames) -> Frames {
+        Frames(self.0 + other.0)
+    }
+}
+
+impl Sub<Frames> for Frames {
+    type Output = Frames;
+    fn sub(self, other: Frames) -> Frames {
+        Frames(self.0 - other.0)
+    }
+}
+
+impl AddAssign<Frames> for Frames {
+    fn add_assign(&mut self, rhs: Frames) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign<Frames> for Frames {
+    fn sub_assign(&mut self, rhs: Frames) {
+        self.0 -= rhs.0;
+    }
+}
@@ -1,8 +1,95 @@
+use super::data_box::DataBox;
 use std::any::Any;
+use std::ops::Deref;
 
+/**
+MetadataSupport 定义了存取元数据的接口，所有的键都使用 `&str`。
+
+MetadataSupport defines the interface for accessing metadata, all keys are `&str`.
+-----
+Example:
+```rust
+# use rusty_studio::core::MetadataSupport;
+# use rusty_studio::timeline::Item;
+let mut item = Item::new();
+item.set_metadata("name", String::from("clip"));
+assert_eq!(item.get_metadata::<String>("name"), Some(String::from("clip")));
+
+item.set_metadata("note", String::from("moved"));
+assert_eq!(item.take_metadata::<String>("note"), Some(String::from("moved")));
+assert_eq!(item.get_metadata::<String>("note"), None);
+
+item.erase_metadata("name");
+assert_eq!(item.get_metadata::<String>("name"), None);
+```
+*/
 pub trait MetadataSupport {
-    fn get_metadata<T: Any + Send + Sync + Clone>(&self, key: &String) -> Option<T>;
-    fn set_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &String, value: T);
-    fn erase_metadata(&mut self, key: &String);
+    fn get_metadata<T: Any + Send + Sync + Clone>(&self, key: &str) -> Option<T>;
+    fn set_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &str, value: T);
+    fn take_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &str) -> Option<T>;
+    fn erase_metadata(&mut self, key: &str);
     fn clear_metadata(&mut self);
 }
+
+/**
+HasDataBox 表示一个持有 `DataBox` 的类型。
+
+只要实现了这两个访问方法，就会自动获得 `MetadataSupport` 的全部功能，
+不需要再逐个地把每个方法委托给 `DataBox`。
+`data_box()` 返回的可以是 `&DataBox`，也可以是 `Ref<DataBox>` 这样的守卫，
+所以把 `DataBox` 放在 `RefCell` 中的类型也可以实现它。
+-----
+HasDataBox represents a type holding a `DataBox`.
+Implementing the two accessors gives the type `MetadataSupport` for free.
+
+Example:
+```rust
+# use rusty_studio::core::{DataBox, HasDataBox, MetadataSupport};
+#[derive(Default)]
+struct Marker {
+    data: DataBox,
+}
+
+impl HasDataBox for Marker {
+    fn data_box(&self) -> impl std::ops::Deref<Target = DataBox> + '_ {
+        &self.data
+    }
+
+    fn data_box_mut(&mut self) -> &mut DataBox {
+        &mut self.data
+    }
+}
+
+let mut marker = Marker::default();
+marker.set_metadata("label", String::from("chapter 1"));
+assert_eq!(marker.get_metadata::<String>("label"), Some(String::from("chapter 1")));
+marker.clear_metadata();
+assert_eq!(marker.get_metadata::<String>("label"), None);
+```
+*/
+pub trait HasDataBox {
+    fn data_box(&self) -> impl Deref<Target = DataBox> + '_;
+    fn data_box_mut(&mut self) -> &mut DataBox;
+}
+
+impl<H: HasDataBox> MetadataSupport for H {
+    fn get_metadata<T: Any + Send + Sync + Clone>(&self, key: &str) -> Option<T> {
+        self.data_box().get(key)
+    }
+
+    fn set_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &str, value: T) {
+        self.data_box_mut().set(key, value);
+    }
+
+    fn take_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &str) -> Option<T> {
+        self.data_box_mut().take(key)
+    }
+
+    fn erase_metadata(&mut self, key: &str) {
+        self.data_box_mut().erase(key);
+    }
+
+    fn clear_metadata(&mut self) {
+        self.data_box_mut().clear();
+    }
+}
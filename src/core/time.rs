@@ -2,9 +2,38 @@
 
 use super::timebase::Timebase;
 use super::timecode_parts::*;
+use std::borrow::Borrow;
 use std::hash::Hash;
 use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
 
+///将时间换算为帧时使用的取整方式。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RoundMode {
+    ///四舍五入到最接近的帧，恰好落在半帧上时向正方向取整。
+    Nearest,
+    ///向下取整。
+    Floor,
+    ///向上取整。
+    Ceil,
+}
+
+///在创建 Time 的输入无效时返回的错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeError {
+    ///输入的数值是 NaN 或无穷大。
+    NonFinite,
+}
+
+impl std::fmt::Display for TimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TimeError::NonFinite => write!(f, "Non-finite Time Value"),
+        }
+    }
+}
+
+impl std::error::Error for TimeError {}
+
 /**
 Time 表示一个时间向量。
 它可以表示一个时刻或一段时长，但是本质上它是表示时间的一维向量。
@@ -49,7 +78,7 @@ Time can also be converted to timecode text by `Time::to_timecode()` or `Time::t
 The form of `hh:mm:ss:ff` is called `timecode`, and the timecode needs to provide `Timebase` information when converting;
 The form of `hh:mm:ss.MMM` is called `timestamp`, where `MMM` is milliseconds, so timestamp does not need timebase information.
 */
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Time {
     data: i128,
 }
@@ -73,6 +102,9 @@ impl Default for Time {
 }
 
 impl Time {
+    ///值为 0 的 Time。
+    pub const ZERO: Time = Time { data: 0 };
+
     ///直接通过一个 i128 毫秒数创建一个新的 Time。
     pub fn new(m: i128) -> Time {
         Time { data: m }
@@ -84,16 +116,153 @@ impl Time {
         Time { data: m }
     }
     
+    /**
+    通过一个 f64 毫秒数创建一个新的 Time，数值会四舍五入到最接近的毫秒。
+
+    和 `Time::from_seconds()` 一样，此方法不会失败：
+    NaN 会被当作 0，无穷大则饱和为 Time 能表示的最大或最小值。
+    -----
+    Construct Time from a f64 milliseconds, rounded to the nearest millisecond.
+    `NaN` becomes 0 and infinities saturate to the extreme values.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    assert_eq!(Time::from_millisecond_f64(1234.6).to_millisecond(), 1235);
+    assert_eq!(Time::from_millisecond_f64(-0.4).to_millisecond(), 0);
+    assert_eq!(Time::from_millisecond_f64(f64::NAN).to_millisecond(), 0);
+    assert_eq!(Time::from_millisecond_f64(f64::INFINITY).to_millisecond(), i128::MAX);
+    ```
+    */
+    pub fn from_millisecond_f64(m: f64) -> Time {
+        Time {
+            data: m.round() as i128,
+        }
+    }
+
     ///转换为毫秒数。其实是直接读取了内部的数据。
     pub fn to_millisecond(&self) -> i128 {
         self.data
     }
 
+    /**
+    转换为 i64 毫秒数，超出 i64 范围时饱和为 `i64::MAX` 或 `i64::MIN`。
+
+    Convert to milliseconds as i64, saturating at the bounds of i64.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    assert_eq!(Time::from_millisecond(1500).to_millis_i64(), 1500);
+    assert_eq!(Time::from_millisecond(-1500).to_millis_i64(), -1500);
+    assert_eq!(Time::from_millisecond(i64::MAX as i128 + 1).to_millis_i64(), i64::MAX);
+    ```
+    */
+    pub fn to_millis_i64(&self) -> i64 {
+        self.data.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /**
+    转换为 u64 毫秒数，负值返回 `None`，超出 u64 范围时饱和为 `u64::MAX`。
+
+    Convert to milliseconds as u64, `None` if negative, saturating at `u64::MAX`.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    assert_eq!(Time::from_millisecond(1500).to_millis_u64(), Some(1500));
+    assert_eq!(Time::from_millisecond(-1).to_millis_u64(), None);
+    assert_eq!(Time::from_millisecond(i128::MAX).to_millis_u64(), Some(u64::MAX));
+    ```
+    */
+    pub fn to_millis_u64(&self) -> Option<u64> {
+        if self.data < 0 {
+            return None;
+        }
+        Some(self.data.min(u64::MAX as i128) as u64)
+    }
+
     ///转换为秒（作为浮点数）。
     pub fn to_second(&self) -> f64 {
         self.data as f64 / 1000.0
     }
 
+    ///判断时间是否为负值。
+    pub fn is_negative(&self) -> bool {
+        self.data < 0
+    }
+
+    /**
+    把时间分解为时、分、秒、毫秒四个部分，以便自行排版。
+
+    分解的是时间的绝对值，正负号需要通过 `Time::is_negative()` 另行判断。
+    和时间戳不同，小时数不会按照 24 小时回绕；超出 i64 范围的小时数会饱和为 `i64::MAX`。
+    -----
+    Decompose the absolute value of the time into hours, minutes, seconds and milliseconds.
+    Use `Time::is_negative()` for the sign. Hours don't wrap at 24,
+    but saturate at `i64::MAX` for times too large to fit.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let time = Time::from_millisecond(3_725_500);
+    assert_eq!(time.components(), (1, 2, 5, 500));
+    assert!(!time.is_negative());
+
+    let time = Time::from_millisecond(-3_725_500);
+    assert_eq!(time.components(), (1, 2, 5, 500));
+    assert!(time.is_negative());
+
+    let time = Time::from_millisecond(i128::MIN);
+    assert_eq!(time.components().0, i64::MAX);
+    ```
+    */
+    pub fn components(&self) -> (i64, u8, u8, u16) {
+        let abs = self.data.unsigned_abs();
+        let ms = (abs % 1000) as u16;
+        let seconds = abs / 1000;
+        let ss = (seconds % 60) as u8;
+        let minutes = seconds / 60;
+        let mm = (minutes % 60) as u8;
+        let hh = i64::try_from(minutes / 60).unwrap_or(i64::MAX);
+        (hh, mm, ss, ms)
+    }
+
+    /**
+    转换为 ISO 8601 格式的时长文本，例如 `PT1H2M3.5S`。
+
+    值为 0 的部分会被省略，时长为 0 时输出 `PT0S`，负值前面会加上 `-`。
+    -----
+    Convert to an ISO 8601 duration, such as `PT1H2M3.5S`.
+    Zero parts are omitted, zero is `PT0S`, and negatives are prefixed with `-`.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    assert_eq!(Time::from_millisecond(3_723_500).to_iso8601_duration(), "PT1H2M3.5S");
+    assert_eq!(Time::from_millisecond(-1_500).to_iso8601_duration(), "-PT1.5S");
+    assert_eq!(Time::from_millisecond(3_600_000).to_iso8601_duration(), "PT1H");
+    assert_eq!(Time::ZERO.to_iso8601_duration(), "PT0S");
+    ```
+    */
+    pub fn to_iso8601_duration(&self) -> String {
+        let (hh, mm, ss, ms) = self.components();
+        let mut result = String::from(if self.is_negative() { "-PT" } else { "PT" });
+        if hh > 0 {
+            result.push_str(&format!("{}H", hh));
+        }
+        if mm > 0 {
+            result.push_str(&format!("{}M", mm));
+        }
+        if ms > 0 {
+            let fraction = format!("{:03}", ms);
+            result.push_str(&format!("{}.{}S", ss, fraction.trim_end_matches('0')));
+        } else if ss > 0 || (hh == 0 && mm == 0) {
+            result.push_str(&format!("{}S", ss));
+        }
+        result
+    }
+
     fn milliseconds_from_seconds(seconds: f64) -> i128 {
         (seconds * 1000.0).round() as i128
     }
@@ -102,6 +271,12 @@ impl Time {
     Construct Time from a f64 seconds.
     Value of seconds will be rounded to the nearest millisecond.
 
+    This conversion is lossy for non-finite input:
+    `NaN` becomes 0 and infinities saturate to the extreme values.
+    Use `Time::try_from_seconds()` to reject them instead.
+
+    The seconds can be passed either by value or by reference.
+
     Example:
     ```rust
     # use rusty_studio::core::Time;
@@ -110,10 +285,164 @@ impl Time {
     let time = Time::from_seconds(1.23456);
     assert_eq!(time.to_millisecond(), 1235);
     ```
+
+    ```rust
+    # use rusty_studio::core::Time;
+    let seconds = vec![1.234, 2.5];
+    let times: Vec<Time> = seconds.iter().map(Time::from_seconds).collect();
+    assert_eq!(times[0].to_millisecond(), 1234);
+    assert_eq!(Time::from_seconds(&2.5), times[1]);
+    ```
+    */
+    pub fn from_seconds<S: Borrow<f64>>(seconds: S) -> Self {
+        Time {
+            data: Self::milliseconds_from_seconds(*seconds.borrow()),
+        }
+    }
+
+    /**
+    从 f64 秒数创建一个新的 Time，但是拒绝 NaN 和无穷大。
+    读取不可信的数据（例如 JSON）时应当使用此方法。
+    -----
+    Construct Time from a f64 seconds, rejecting `NaN` and infinities.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,TimeError};
+    assert_eq!(Time::try_from_seconds(1.5).unwrap().to_millisecond(), 1500);
+    assert_eq!(Time::try_from_seconds(f64::NAN), Err(TimeError::NonFinite));
+    assert_eq!(Time::try_from_seconds(f64::INFINITY), Err(TimeError::NonFinite));
+    ```
+    */
+    pub fn try_from_seconds(seconds: f64) -> Result<Self, TimeError> {
+        if !seconds.is_finite() {
+            return Err(TimeError::NonFinite);
+        }
+        Ok(Self::from_seconds(seconds))
+    }
+
+    /**
+    通过时、分、秒、毫秒创建一个新的 Time。
+
+    各个部分直接换算成毫秒后相加，所以允许负值，也允许超出常规范围的值，例如 90 分钟。
+    -----
+    Construct Time from hours, minutes, seconds and milliseconds.
+    The parts are simply summed up, so negatives and out-of-range values (like 90 minutes) are allowed.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let time = Time::from_hms_ms(1, 2, 3, 500);
+    assert_eq!(time.to_millisecond(), 3_723_500);
+
+    let time = Time::from_hms_ms(0, 90, 0, 0);
+    assert_eq!(time, Time::from_hms_ms(1, 30, 0, 0));
+    assert_eq!(time.to_timestamp(), "01:30:00.000");
+    ```
+    */
+    pub fn from_hms_ms(hh: i64, mm: i64, ss: i64, ms: i64) -> Time {
+        let mut data = hh as i128 * 60 * 60 * 1000;
+        data += mm as i128 * 60 * 1000;
+        data += ss as i128 * 1000;
+        data += ms as i128;
+        Time { data }
+    }
+
+    /**
+    计算两个时间的中点。
+
+    结果精确到毫秒，恰好落在半毫秒上时向正方向取整，所以交换参数的顺序不会影响结果。
+    计算时先把两个时间分别减半再相加，再根据余数修正，所以即使是 i128 的极端值也不会溢出。
+    -----
+    Calculate the midpoint between two times.
+    Halves are rounded towards positive, so the order of the arguments doesn't matter.
+    Each time is halved before adding, so even the extreme i128 values don't overflow.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let a = Time::from_millisecond(1000);
+    let b = Time::from_millisecond(2000);
+    assert_eq!(Time::midpoint(a, b).to_millisecond(), 1500);
+
+    let a = Time::from_millisecond(0);
+    let b = Time::from_millisecond(1001);
+    assert_eq!(Time::midpoint(a, b).to_millisecond(), 501);
+    assert_eq!(Time::midpoint(b, a).to_millisecond(), 501);
+
+    let a = Time::from_millisecond(-1001);
+    let b = Time::from_millisecond(0);
+    assert_eq!(Time::midpoint(a, b).to_millisecond(), -500);
+
+    let a = Time::from_millisecond(i128::MIN);
+    let b = Time::from_millisecond(i128::MAX);
+    assert_eq!(Time::midpoint(a, b).to_millisecond(), 0);
+    assert_eq!(Time::midpoint(b, b).to_millisecond(), i128::MAX);
+    ```
+    */
+    pub fn midpoint(a: Time, b: Time) -> Time {
+        let half = a.data.div_euclid(2) + b.data.div_euclid(2);
+        let remainder = a.data.rem_euclid(2) + b.data.rem_euclid(2);
+        Time {
+            data: half + (remainder + 1).div_euclid(2),
+        }
+    }
+
+    /**
+    计算两个时间之间的距离，结果总是非负的。
+    距离超出 i128 范围时饱和为最大值。
+
+    Calculate the distance between two times, which is never negative.
+    Distances too large for an i128 saturate to the maximum value.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let a = Time::from_millisecond(1200);
+    let b = Time::from_millisecond(-300);
+    assert_eq!(a.abs_diff(b).to_millisecond(), 1500);
+    assert_eq!(b.abs_diff(a).to_millisecond(), 1500);
+
+    let a = Time::from_millisecond(i128::MIN);
+    let b = Time::from_millisecond(i128::MAX);
+    assert_eq!(a.abs_diff(b).to_millisecond(), i128::MAX);
+    ```
+    */
+    pub fn abs_diff(&self, other: Time) -> Time {
+        let diff = self.data.abs_diff(other.data);
+        Time {
+            data: diff.min(i128::MAX as u128) as i128,
+        }
+    }
+
+    /**
+    把时间映射到循环区间 `[start, end)` 之中，用于循环播放。
+
+    早于 start 的时间也会正确地从区间末尾倒数回来。
+    如果循环区间的长度不大于 0，直接返回 start。
+    -----
+    Map the time into the loop region `[start, end)`.
+    Times before `start` wrap backwards from the end.
+    If the loop length is not positive, `start` is returned.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let start = Time::from_millisecond(1000);
+    let end = Time::from_millisecond(3000);
+    assert_eq!(Time::from_millisecond(3500).wrap_into(start, end).to_millisecond(), 1500);
+    assert_eq!(Time::from_millisecond(3000).wrap_into(start, end).to_millisecond(), 1000);
+    assert_eq!(Time::from_millisecond(500).wrap_into(start, end).to_millisecond(), 2500);
+    assert_eq!(Time::from_millisecond(500).wrap_into(start, start), start);
+    ```
     */
-    pub fn from_seconds(seconds: f64) -> Self {
+    pub fn wrap_into(&self, start: Time, end: Time) -> Time {
+        let length = end.data - start.data;
+        if length <= 0 {
+            return start;
+        }
         Time {
-            data: Self::milliseconds_from_seconds(seconds),
+            data: start.data + (self.data - start.data).rem_euclid(length),
         }
     }
 
@@ -140,16 +469,169 @@ impl Time {
     */
     pub fn from_timecode(timecode: &str, timebase: &Timebase) -> Result<Self, TimecodeFormatError> {
         let parts = TimecodeParts::from_timecode(timecode)?;
+        Ok(Self::from_timecode_parts(&parts, timebase))
+    }
+
+    /**
+    从时间码文本和帧速率创建一个新的 Time，同时返回推断出的时基。
+
+    和 `Time::from_timecode()` 不同，此方法不需要完整的 `Timebase`，
+    是否丢帧直接由时间码中的分隔符决定：`;` 表示丢帧，`:` 表示不丢帧。
+    这样可以避免传入的时基和时间码文本不一致的问题。
+    返回的时基可以用于之后的转换，例如把时间重新输出为相同形式的时间码。
+    -----
+    Create a new Time from timecode text and fps, together with the inferred timebase.
+    Drop frame is inferred from the separator in the timecode text (`;` for drop frame)
+    instead of being passed in by a `Timebase`.
+    The returned timebase can be used for later conversions.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let (time, timebase) = Time::from_timecode_with_fps("00:00:01;00", 30).unwrap();
+    assert_eq!(time.to_millisecond(), 1000);
+    assert_eq!(timebase, Timebase{fps:30, drop_frame:true});
+    assert_eq!(time.to_timecode(&timebase), "00:00:01;00");
+
+    let (time, timebase) = Time::from_timecode_with_fps("00:00:01:15", 30).unwrap();
+    assert_eq!(time.to_millisecond(), 1500);
+    assert_eq!(timebase, Timebase{fps:30, drop_frame:false});
+    assert_eq!(time.to_timecode(&timebase), "00:00:01:15");
+
+    assert!(Time::from_timecode_with_fps("something wrong", 30).is_err());
+    ```
+    */
+    pub fn from_timecode_with_fps(
+        timecode: &str,
+        fps: u8,
+    ) -> Result<(Self, Timebase), TimecodeFormatError> {
+        let parts = TimecodeParts::from_timecode(timecode)?;
+        let timebase = Timebase {
+            fps,
+            drop_frame: parts.drop_frame,
+        };
+        Ok((Self::from_timecode_parts(&parts, &timebase), timebase))
+    }
+
+    /**
+    从不完整的时间码文本创建一个新的 Time，缺少的高位部分视为 0。
+
+    支持以下几种形式：
+    - 完整的时间码 `hh:mm:ss:ff`（或 `hh:mm:ss;ff`），等同于 `Time::from_timecode()`；
+    - `hh:mm:ss`、`mm:ss` 或 `ss`，冒号分隔的部分从秒开始向高位对齐，
+      所以 `"05:30"` 表示 5 分 30 秒，`"00:12"` 表示 12 秒（而不是 12 帧）；
+    - 以 `f` 结尾的帧数，例如 `"12f"` 或 `"+12f"`，也可以接在前面的形式之后，例如 `"01:00+12f"`；
+    - 以冒号开头的帧数 `:ff`，例如 `":12"` 表示 12 帧。
+
+    无法识别的文本会返回 `TimecodeFormatError` 错误，`"01:00+f"` 这样缺少帧数的文本也是如此。
+
+    注意：一些剪辑软件允许输入 `505` 表示 5 秒 5 帧，即从帧开始向高位对齐。
+    此方法不支持这种写法，因为它和 `ss` 的形式冲突：不带分隔符的数字总是表示秒数，`"505"` 就是 505 秒。
+    -----
+    Create a new Time from partial timecode text, missing higher fields are zero.
+
+    Supported forms are the full timecode `hh:mm:ss:ff`, `hh:mm:ss`, `mm:ss` and `ss`
+    (fields are aligned from seconds, so `"00:12"` means 12 seconds, not 12 frames),
+    a frame count suffixed with `f` such as `"12f"`, `"+12f"` or `"01:00+12f"`,
+    and a frame count prefixed with a colon such as `":12"`.
+    Unrecognized text, including a missing frame count like `"01:00+f"`, is an error.
+
+    Note: the editor shorthand `505` for 5 seconds 5 frames (digits aligned from frames)
+    is not supported, since it conflicts with the `ss` form: `"505"` is 505 seconds.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    let parse = |text| Time::from_partial_timecode(text, &timebase).unwrap().to_millisecond();
+    assert_eq!(parse("05:30"), 330_000);
+    assert_eq!(parse("00:12"), 12_000);
+    assert_eq!(parse("1:00:00"), 3_600_000);
+    assert_eq!(parse("+12f"), 500);
+    assert_eq!(parse("01:00+12f"), 60_500);
+    assert_eq!(parse("00:00:01:12"), 1_500);
+    assert_eq!(parse(":12"), 500);
+    assert_eq!(parse("505"), 505_000);
+    assert!(Time::from_partial_timecode("01:00+f", &timebase).is_err());
+    assert!(Time::from_partial_timecode("f", &timebase).is_err());
+    assert!(Time::from_partial_timecode(":", &timebase).is_err());
+    assert!(Time::from_partial_timecode("something wrong", &timebase).is_err());
+    assert!(Time::from_partial_timecode("", &timebase).is_err());
+    assert!(Time::from_partial_timecode("18446744073709551615", &timebase).is_err());
+    assert!(Time::from_partial_timecode("x00:00:01:00y", &timebase).is_err());
+    ```
+    */
+    pub fn from_partial_timecode(
+        timecode: &str,
+        timebase: &Timebase,
+    ) -> Result<Self, TimecodeFormatError> {
+        let text = timecode.trim();
+        let (body, frames) = match text.strip_suffix(['f', 'F']) {
+            Some(rest) => match rest.rsplit_once('+') {
+                Some((body, frames)) => (body, Some(frames)),
+                None => ("", Some(rest)),
+            },
+            None => match text.strip_prefix(':') {
+                Some(frames) => ("", Some(frames)),
+                None => (text, None),
+            },
+        };
+        if body.is_empty() && frames.is_none() {
+            return Err(TimecodeFormatError);
+        }
+
+        let mut fields = Vec::new();
+        if !body.is_empty() {
+            let parts: Vec<&str> = body.split([':', ';']).collect();
+            if parts.len() == 4 {
+                let is_field =
+                    |part: &&str| part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit());
+                if !parts.iter().all(is_field) {
+                    return Err(TimecodeFormatError);
+                }
+                let time = Self::from_timecode(body, timebase)?;
+                return Ok(time + Self::from_partial_frames(frames, timebase)?);
+            }
+            for field in body.split(':') {
+                let value: u64 = field.trim().parse().map_err(|_| TimecodeFormatError)?;
+                fields.push(i64::try_from(value).map_err(|_| TimecodeFormatError)?);
+            }
+            if fields.len() > 3 {
+                return Err(TimecodeFormatError);
+            }
+        }
+
+        let mut hms = [0i64; 3];
+        hms[3 - fields.len()..].copy_from_slice(&fields);
+        let time = Self::from_hms_ms(hms[0], hms[1], hms[2], 0);
+        Ok(time + Self::from_partial_frames(frames, timebase)?)
+    }
+
+    fn from_partial_frames(
+        frames: Option<&str>,
+        timebase: &Timebase,
+    ) -> Result<Self, TimecodeFormatError> {
+        let Some(frames) = frames else {
+            return Ok(Time::default());
+        };
+        let frames: u64 = frames.trim().parse().map_err(|_| TimecodeFormatError)?;
+        Ok(Time {
+            data: timebase.milliseconds_from_frames(frames),
+        })
+    }
+
+    fn from_timecode_parts(parts: &TimecodeParts, timebase: &Timebase) -> Self {
         let mut ms = parts.hh as i128 * 60 * 60 * 1000;
         ms += parts.mm as i128 * 60 * 1000;
         ms += parts.ss as i128 * 1000;
         ms += timebase.milliseconds_from_frames(parts.ff as u64);
-        Ok(Time { data: ms })
+        Time { data: ms }
     }
 
     /**
     将 Time 转换为时间码文本。
     其作用和 `Time::from_timecode()` 相反。
+    帧数四舍五入到最接近的帧，相当于使用 `RoundMode::Nearest` 调用 `Time::to_timecode_rounded()`。
 
     Example:
     ```rust
@@ -160,14 +642,50 @@ impl Time {
     ```
     */
     pub fn to_timecode(&self, timebase: &Timebase) -> String {
-        let ms = (self.data % 1000) as u32;
-        let ff = timebase.frames_from_milliseconds(ms as i128) as u32;
-        let seconds = self.to_second() as u64;
-        let ss = (seconds % 60) as u8;
-        let minutes = seconds / 60;
-        let mm = (minutes % 60) as u8;
-        let hours = minutes / 60;
-        let hh = (hours % 24) as u8;
+        self.to_timecode_rounded(timebase, RoundMode::Nearest)
+    }
+
+    /**
+    按照指定的取整方式将 Time 转换为时间码文本。
+
+    取整只在计算总帧数时进行一次，时、分、秒都是从总帧数推算出来的，
+    所以即使时间非常接近下一秒，也不会出现帧数等于帧速率的时间码。
+    和时间戳一样，小时数按照 24 小时回绕，负值则从 24 小时倒数回来。
+    帧速率为 0 的时基没有帧，总是输出 `00:00:00:00`。
+    -----
+    Convert Time to timecode text with the given rounding mode.
+    The rounding is applied once to the total frame count,
+    hours, minutes and seconds are all derived from it.
+    A timebase with fps 0 has no frames and always gives `00:00:00:00`.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase,RoundMode};
+    let timebase = Timebase::new(24);
+    let time = Time::from_millisecond(999);
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Nearest), "00:00:01:00");
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Floor), "00:00:00:23");
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Ceil), "00:00:01:00");
+
+    let time = Time::from_millisecond(1040);
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Nearest), "00:00:01:01");
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Floor), "00:00:01:00");
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Ceil), "00:00:01:01");
+
+    let time = Time::from_seconds(f64::INFINITY);
+    assert_eq!(time.to_timecode_rounded(&timebase, RoundMode::Ceil).len(), 11);
+    ```
+    */
+    pub fn to_timecode_rounded(&self, timebase: &Timebase, mode: RoundMode) -> String {
+        let fps = timebase.fps as i128;
+        let total = self.frames_rounded(timebase, mode);
+        let ff = total.rem_euclid(fps.max(1)) as u32;
+        let seconds = total.div_euclid(fps.max(1));
+        let ss = seconds.rem_euclid(60) as u8;
+        let minutes = seconds.div_euclid(60);
+        let mm = minutes.rem_euclid(60) as u8;
+        let hours = minutes.div_euclid(60);
+        let hh = hours.rem_euclid(24) as u8;
         TimecodeParts {
             hh,
             mm,
@@ -178,16 +696,229 @@ impl Time {
         .to_timecode()
     }
 
+    /**
+    从自零点起的总帧数创建一个新的 Time，结果四舍五入到毫秒。
+
+    和时间码不同，总帧数只是一个单独的数字，常见于渲染流程中。
+    帧速率为 0 的时基没有帧，任何帧数都换算为 `Time::ZERO`；
+    `Time::to_total_frames()` 在这种时基下也总是返回 0。
+    -----
+    Construct Time from the total frame count since zero,
+    the result is rounded to the nearest millisecond.
+    A timebase with fps 0 has no frames, so any count becomes `Time::ZERO`,
+    and `Time::to_total_frames()` always returns 0 for it.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    let time = Time::from_total_frames(1440, &timebase);
+    assert_eq!(time.to_millisecond(), 60000);
+    assert_eq!(time.to_total_frames(&timebase), 1440);
+
+    let timebase = Timebase::new(0);
+    assert_eq!(Time::from_total_frames(1440, &timebase), Time::ZERO);
+    assert_eq!(Time::from_millisecond(60000).to_total_frames(&timebase), 0);
+    ```
+    */
+    pub fn from_total_frames(frames: i64, timebase: &Timebase) -> Time {
+        let fps = timebase.fps as i128;
+        if fps == 0 {
+            return Time::ZERO;
+        }
+        Time {
+            data: (frames as i128 * 2000 + fps).div_euclid(fps * 2),
+        }
+    }
+
+    /**
+    计算自零点起最接近的总帧数，恰好落在半帧上时向正方向取整。
+    其作用和 `Time::from_total_frames()` 相反。
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    assert_eq!(Time::from_millisecond(60000).to_total_frames(&timebase), 1440);
+    assert_eq!(Time::from_millisecond(-1000).to_total_frames(&timebase), -24);
+    ```
+
+    超出 i64 范围的帧数会饱和为 `i64::MIN` 或 `i64::MAX`。
+
+    Frame counts beyond the i64 range saturate to `i64::MIN` or `i64::MAX`.
+
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    assert_eq!(Time::from_seconds(f64::INFINITY).to_total_frames(&timebase), i64::MAX);
+    assert_eq!(Time::from_seconds(f64::NEG_INFINITY).to_total_frames(&timebase), i64::MIN);
+    ```
+    */
+    pub fn to_total_frames(&self, timebase: &Timebase) -> i64 {
+        let total = self.frames_rounded(timebase, RoundMode::Nearest);
+        total.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    ///先拆分为整秒和余下的毫秒再分别乘以帧速率，避免极端值溢出。
+    pub(crate) fn frames_rounded(&self, timebase: &Timebase, mode: RoundMode) -> i128 {
+        let fps = timebase.fps as i128;
+        let seconds = self.data.div_euclid(1000);
+        let remainder = self.data.rem_euclid(1000) * fps;
+        let frames = match mode {
+            RoundMode::Nearest => (remainder * 2 + 1000).div_euclid(2000),
+            RoundMode::Floor => remainder.div_euclid(1000),
+            RoundMode::Ceil => (remainder + 999).div_euclid(1000),
+        };
+        seconds * fps + frames
+    }
+
+    /**
+    生成同时显示总帧数和毫秒数的文本，便于调试帧和毫秒不一致的问题。
+
+    Generate text showing both the total frames and the milliseconds, useful for debugging.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let time = Time::from_millisecond(5000);
+    assert_eq!(time.to_frame_string(&Timebase::new(24)), "120 frames (5000ms)");
+    ```
+    */
+    pub fn to_frame_string(&self, timebase: &Timebase) -> String {
+        format!("{} frames ({}ms)", self.to_total_frames(timebase), self.data)
+    }
+
+    /**
+    转换为胶片工作流程中使用的“英尺+帧”的形式，例如 `"123+04"`。
+
+    `frames_per_foot` 是每英尺的帧数，35mm 胶片通常为 16。为 0 时按 1 处理。
+    负值会带有 `-` 号。
+    -----
+    Convert to feet+frames used in film workflows, such as `"123+04"`.
+    `frames_per_foot` is usually 16 for 35mm film, 0 is treated as 1.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    assert_eq!(Time::from_millisecond(10_000).to_feet_frames(&timebase, 16), "15+00");
+    assert_eq!(Time::from_millisecond(1_000).to_feet_frames(&timebase, 16), "1+08");
+    assert_eq!(Time::from_millisecond(-1_000).to_feet_frames(&timebase, 16), "-1+08");
+    ```
+    */
+    pub fn to_feet_frames(&self, timebase: &Timebase, frames_per_foot: u32) -> String {
+        let frames_per_foot = frames_per_foot.max(1) as u64;
+        let total = self.to_total_frames(timebase);
+        let sign = if total < 0 { "-" } else { "" };
+        let total = total.unsigned_abs();
+        format!(
+            "{}{}+{:02}",
+            sign,
+            total / frames_per_foot,
+            total % frames_per_foot
+        )
+    }
+
+    /**
+    按照倍数缩放时间，并对齐到最接近的帧。
+
+    直接用 Time 乘以一个非整数通常会破坏帧对齐，
+    此方法在缩放之后重新对齐到帧，保证变速后的片段仍然精确到帧。
+    -----
+    Scale the time by `factor`, then snap it to the nearest frame.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    let time = Time::from_millisecond(1000);
+    let scaled = time.scale_snapped(1.5, &timebase);
+    assert_eq!(scaled.to_total_frames(&timebase), 36);
+    assert_eq!(scaled.to_millisecond(), 1500);
+
+    let scaled = time.scale_snapped(1.01, &timebase);
+    assert_eq!(scaled, Time::from_total_frames(24, &timebase));
+    ```
+    */
+    pub fn scale_snapped(&self, factor: f64, timebase: &Timebase) -> Time {
+        let scaled = *self * factor;
+        Self::from_total_frames(scaled.to_total_frames(timebase), timebase)
+    }
+
+    /**
+    按照整帧步进时间。
+
+    计算时先把当前时间对齐到最接近的帧，再加上帧数并换算回毫秒。
+    每一次都是从帧数直接换算，所以反复地单帧步进不会累积误差。
+    步进后的总帧数超出 i64 范围时会饱和，而不会溢出。
+
+    和 `Timebase` 的其它功能一样，丢帧的时基也按照整数帧速率计算，
+    但是步进时会跳过丢帧时间码中不存在的帧号（参见 `Timebase::valid_frame_numbers()`），
+    所以 `00:00:59;29` 的下一帧是 `00:01:00;02`。从不存在的帧号出发时，视为从其后第一个实际出现的帧号出发。
+    -----
+    Step the time by whole frames.
+
+    The time is aligned to the nearest frame first, then the frames are added
+    and converted back to milliseconds from the total frame count,
+    so repeated single-frame steps don't accumulate rounding errors.
+    The stepped frame count saturates at the i64 range instead of overflowing.
+
+    Like other parts of `Timebase`, drop frame timebases are calculated with the integer fps,
+    but stepping skips the frame numbers that drop frame timecode omits
+    (see `Timebase::valid_frame_numbers()`), so the frame after `00:00:59;29` is `00:01:00;02`.
+    Stepping from an omitted frame number starts from the next one that exists.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    let mut time = Time::default();
+    for _ in 0..48 {
+        time = time.step_frames(1, &timebase);
+    }
+    assert_eq!(time.to_millisecond(), 2000);
+    assert_eq!(time.step_frames(-48, &timebase).to_millisecond(), 0);
+    assert_eq!(time.step_frames(1, &Timebase::new(0)), Time::ZERO);
+
+    let time = Time::from_seconds(f64::INFINITY).step_frames(1, &timebase);
+    assert_eq!(time, Time::from_total_frames(i64::MAX, &timebase));
+    ```
+
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::from_real_fps(29.97);
+    let time = Time::from_timecode("00:00:59;29", &timebase).unwrap();
+    let next = time.step_frames(1, &timebase);
+    assert_eq!(next.to_timecode(&timebase), "00:01:00;02");
+    assert_eq!(next.step_frames(-1, &timebase), time);
+    assert_eq!(time.step_frames(3, &timebase).to_timecode(&timebase), "00:01:00;04");
+
+    let time = Time::from_timecode("00:09:59;29", &timebase).unwrap();
+    assert_eq!(time.step_frames(1, &timebase).to_timecode(&timebase), "00:10:00;00");
+    ```
+    */
+    pub fn step_frames(&self, frames: i64, timebase: &Timebase) -> Time {
+        let current = self.frames_rounded(timebase, RoundMode::Nearest);
+        let ordinal = timebase.frame_ordinal(current) + frames as i128;
+        let total = timebase.frames_from_ordinal(ordinal);
+        let total = total.clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+        Self::from_total_frames(total, timebase)
+    }
+
     /**
     从时间戳文本创建一个新的 Time。
     时间戳文本使用正则表达式判断并解析，如果解析失败，将会返回一个 `TimecodeFormatError` 错误。
 
-    注意：`时间戳` 在本工具集中特指 `hh:mm:ss:MMM` 的形式。
+    注意：`时间戳` 在本工具集中特指 `hh:mm:ss.MMM` 的形式。
+    解析时是宽松的，毫秒之前的分隔符可以是 `.`、`,`、`:` 或 `;`（例如 SRT 字幕使用 `,`）；
+    但是 `Time::to_timestamp()` 总是输出 `.`。
     ---
     Create a new Time from timestamp text.
     The timestamp text is parsed using a regular expression and checked.
 
-    Note: `timestamp` refers to the form of `hh:mm:ss:MMM` in this toolset.
+    Note: `timestamp` refers to the form of `hh:mm:ss.MMM` in this toolset.
+    Input is lenient: the separator before milliseconds can be `.`, `,`, `:` or `;`,
+    but `Time::to_timestamp()` always outputs `.`.
     -----
     Example:
     ```rust
@@ -199,6 +930,15 @@ impl Time {
     let time = Time::from_timestamp("something wrong");
     assert!(time.is_err());
     ```
+
+    ```rust
+    # use rusty_studio::core::Time;
+    for text in ["00:00:05.150", "00:00:05,150", "00:00:05:150", "00:00:05;150"] {
+        let time = Time::from_timestamp(text).unwrap();
+        assert_eq!(time.to_millisecond(), 5150);
+        assert_eq!(time.to_timestamp(), "00:00:05.150");
+    }
+    ```
     */
     pub fn from_timestamp(timecode: &str) -> Result<Self, TimecodeFormatError> {
         let parts = TimecodeParts::from_timestamp(timecode)?;
@@ -237,6 +977,114 @@ impl Time {
         }
         .to_timestamp()
     }
+
+    /**
+    将 Time 转换为指定小数位数的时间戳文本。
+
+    `digits` 为秒之后保留的小数位数（0 到 3，超过 3 按 3 处理），时间会先四舍五入到对应的精度。
+    位数为 0 时不输出小数点。负值和 `Time::to_signed_timestamp()` 一样按绝对值排版并加上 `-` 号，
+    正值不加符号。对于非负的时间，`Time::to_timestamp()` 相当于保留 3 位小数。
+    -----
+    Convert Time to timestamp text with the given number of fractional digits (0 to 3).
+    The time is rounded to that precision first.
+    Negatives are formatted from the absolute value with a `-` prefix, like `Time::to_signed_timestamp()`.
+    For non-negative times, `Time::to_timestamp()` is the same as 3 digits.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    let time = Time::from_millisecond(5550);
+    assert_eq!(time.to_timestamp_precision(0), "00:00:06");
+    assert_eq!(time.to_timestamp_precision(1), "00:00:05.6");
+    assert_eq!(time.to_timestamp_precision(2), "00:00:05.55");
+    assert_eq!(time.to_timestamp_precision(3), time.to_timestamp());
+    assert_eq!(Time::from_millisecond(5050).to_timestamp_precision(2), "00:00:05.05");
+
+    let time = Time::from_millisecond(-1500);
+    assert_eq!(time.to_timestamp_precision(1), "-00:00:01.5");
+    assert_eq!(time.to_timestamp_precision(0), "-00:00:02");
+    ```
+    */
+    pub fn to_timestamp_precision(&self, digits: u8) -> String {
+        let digits = digits.min(3) as usize;
+        let unit = 10u128.pow(3 - digits as u32);
+        let abs = self.data.unsigned_abs();
+        let abs = (abs / unit + u128::from(abs % unit * 2 >= unit)) * unit;
+        let sign = if self.data < 0 { "-" } else { "" };
+        let parts = Self::timestamp_parts(abs);
+        let head = format!("{}{:02}:{:02}:{:02}", sign, parts.hh, parts.mm, parts.ss);
+        if digits == 0 {
+            head
+        } else {
+            let fraction = parts.ff as u128 / unit;
+            format!("{}.{:0width$}", head, fraction, width = digits)
+        }
+    }
+
+    /**
+    将 Time 转换为带有正负号的时间戳文本。
+
+    `to_timestamp()` 不能正确处理负值，而此方法总是会输出 `+` 或 `-`，
+    适合在需要明确时间方向的场合使用，例如序列化或比较差异。零值输出为 `+`。
+    -----
+    Convert Time to timestamp text which always has a sign (`+` or `-`).
+    Zero is rendered with `+`.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    assert_eq!(Time::from_millisecond(1500).to_signed_timestamp(), "+00:00:01.500");
+    assert_eq!(Time::from_millisecond(-1500).to_signed_timestamp(), "-00:00:01.500");
+    assert_eq!(Time::default().to_signed_timestamp(), "+00:00:00.000");
+    assert!(Time::from_millisecond(i128::MIN).to_signed_timestamp().starts_with('-'));
+    ```
+    */
+    pub fn to_signed_timestamp(&self) -> String {
+        let sign = if self.data < 0 { "-" } else { "+" };
+        let parts = Self::timestamp_parts(self.data.unsigned_abs());
+        format!("{}{}", sign, parts.to_timestamp())
+    }
+
+    ///把毫秒数的绝对值分解为时间戳的各个部分，小时数按照 24 小时回绕。
+    fn timestamp_parts(abs: u128) -> TimecodeParts {
+        let seconds = abs / 1000;
+        let minutes = seconds / 60;
+        TimecodeParts {
+            hh: (minutes / 60 % 24) as u8,
+            mm: (minutes % 60) as u8,
+            ss: (seconds % 60) as u8,
+            ff: (abs % 1000) as u32,
+            drop_frame: false,
+        }
+    }
+}
+
+/**
+Time 的调试输出同时显示时间戳和毫秒数，例如 `Time(00:00:05.500 / 5500ms)`，
+负值的时间戳带有 `-` 号。
+
+The debug output of Time shows both the timestamp and the milliseconds.
+
+Example:
+```rust
+# use rusty_studio::core::Time;
+let time = Time::from_millisecond(5500);
+assert_eq!(format!("{:?}", time), "Time(00:00:05.500 / 5500ms)");
+let time = Time::from_millisecond(-1500);
+assert_eq!(format!("{:?}", time), "Time(-00:00:01.500 / -1500ms)");
+let time = Time::from_seconds(f64::NEG_INFINITY);
+assert_eq!(format!("{:?}", time), format!("Time(-16:41:45.728 / {}ms)", i128::MIN));
+```
+*/
+impl std::fmt::Debug for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let timestamp = if self.data < 0 {
+            self.to_signed_timestamp()
+        } else {
+            self.to_timestamp()
+        };
+        write!(f, "Time({} / {}ms)", timestamp, self.data)
+    }
 }
 
 impl From<i128> for Time {
@@ -341,3 +1189,152 @@ impl SubAssign<Time> for Time {
         self.data -= rhs.data;
     }
 }
+
+/**
+和标准库中的数字类型一样，Time 也可以通过引用进行加减运算。
+
+Like the std numeric types, Time can also be added or subtracted by reference.
+
+Example:
+```rust
+# use rusty_studio::core::Time;
+let t1 = Time::from_millisecond(1000);
+let t2 = Time::from_millisecond(2000);
+assert_eq!((&t1 + &t2).to_millisecond(), 3000);
+assert_eq!((&t1 - &t2).to_millisecond(), -1000);
+assert_eq!((t1 + &t2).to_millisecond(), 3000);
+assert_eq!((&t1 - t2).to_millisecond(), -1000);
+
+let times = vec![t1, t2];
+let mut total = Time::default();
+for t in &times {
+    total += t;
+}
+assert_eq!(total.to_millisecond(), 3000);
+total -= &t1;
+assert_eq!(total.to_millisecond(), 2000);
+```
+*/
+impl Add<&Time> for &Time {
+    type Output = Time;
+    fn add(self, other: &Time) -> Time {
+        *self + *other
+    }
+}
+
+impl Add<&Time> for Time {
+    type Output = Time;
+    fn add(self, other: &Time) -> Time {
+        self + *other
+    }
+}
+
+impl Add<Time> for &Time {
+    type Output = Time;
+    fn add(self, other: Time) -> Time {
+        *self + other
+    }
+}
+
+impl Sub<&Time> for &Time {
+    type Output = Time;
+    fn sub(self, other: &Time) -> Time {
+        *self - *other
+    }
+}
+
+impl Sub<&Time> for Time {
+    type Output = Time;
+    fn sub(self, other: &Time) -> Time {
+        self - *other
+    }
+}
+
+impl Sub<Time> for &Time {
+    type Output = Time;
+    fn sub(self, other: Time) -> Time {
+        *self - other
+    }
+}
+
+impl AddAssign<&Time> for Time {
+    fn add_assign(&mut self, rhs: &Time) {
+        self.data += rhs.data;
+    }
+}
+
+impl SubAssign<&Time> for Time {
+    fn sub_assign(&mut self, rhs: &Time) {
+        self.data -= rhs.data;
+    }
+}
+
+/**
+返回一组时间中最早的一个，没有任何时间时返回 `None`。
+
+Return the earliest time of the given times, or `None` if there are none.
+
+Example:
+```rust
+# use rusty_studio::core::{Time, earliest, latest};
+let times = vec![
+    Time::from_millisecond(3000),
+    Time::from_millisecond(-500),
+    Time::from_millisecond(1200),
+];
+assert_eq!(earliest(times.clone()), Some(Time::from_millisecond(-500)));
+assert_eq!(latest(times), Some(Time::from_millisecond(3000)));
+
+assert_eq!(earliest(Vec::<Time>::new()), None);
+assert_eq!(latest(Vec::<Time>::new()), None);
+```
+*/
+pub fn earliest<I: IntoIterator<Item = Time>>(times: I) -> Option<Time> {
+    times.into_iter().min()
+}
+
+///返回一组时间中最晚的一个，没有任何时间时返回 `None`。
+pub fn latest<I: IntoIterator<Item = Time>>(times: I) -> Option<Time> {
+    times.into_iter().max()
+}
+
+/**
+Time 可以直接和 i128 毫秒数比较，两个方向都可以。
+
+Time can be compared with milliseconds as i128 directly, both ways.
+
+Example:
+```rust
+# use rusty_studio::core::Time;
+let time = Time::from_millisecond(500);
+assert!(time < 1000);
+assert!(time >= 500);
+assert!(time == 500);
+assert!(1000 > time);
+assert!(500 == time);
+assert!(-1 < time);
+```
+*/
+impl PartialEq<i128> for Time {
+    fn eq(&self, other: &i128) -> bool {
+        self.data == *other
+    }
+}
+
+impl PartialEq<Time> for i128 {
+    fn eq(&self, other: &Time) -> bool {
+        *self == other.data
+    }
+}
+
+impl PartialOrd<i128> for Time {
+    fn partial_cmp(&self, other: &i128) -> Option<std::cmp::Ordering> {
+        self.data.partial_cmp(other)
+    }
+}
+
+impl PartialOrd<Time> for i128 {
+    fn partial_cmp(&self, other: &Time) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(&other.data)
+    }
+}
@@ -1,5 +1,21 @@
 #![allow(dead_code)]
 
+use super::time::{RoundMode, Time};
+use std::ops::Range;
+use std::str::FromStr;
+
+///在时基字符串解析出错时抛出的错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimebaseFormatError;
+
+impl std::fmt::Display for TimebaseFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Invalid Timebase Format")
+    }
+}
+
+impl std::error::Error for TimebaseFormatError {}
+
 /**
 Timebase 时一个简单的结构体，保存了帧速率和是否丢帧的时基信息。
 
@@ -78,7 +94,7 @@ impl Timebase {
     }
 
     /**
-    根据 fps 统计帧数占用的毫秒数。
+    根据 fps 统计帧数占用的毫秒数。帧速率为 0 时没有帧，总是返回 0。
     Calculate the number of milliseconds of a mount of frames, depending on fps.
 
     Example:
@@ -88,9 +104,13 @@ impl Timebase {
     let frames = 100;
     let ms = timebase.milliseconds_from_frames(frames);
     assert_eq!(ms,4167);
+    assert_eq!(Timebase::new(0).milliseconds_from_frames(frames), 0);
     ```
     */
     pub fn milliseconds_from_frames(&self, frames: u64) -> i128 {
+        if self.fps == 0 {
+            return 0;
+        }
         ((frames as f64 / self.fps as f64) * 1000.0).round() as i128
     }
 
@@ -109,6 +129,186 @@ impl Timebase {
         let seconds = ms as f64 / 1000.0;
         (seconds * self.fps as f64).round() as u64
     }
+
+    /**
+    把此时基下的帧数换算为另一个时基下相同时长的帧数，换算通过毫秒进行。
+
+    Convert a number of frames to the frame count of the same duration in another timebase.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Timebase;
+    let film = Timebase::new(24);
+    let video = Timebase::new(30);
+    assert_eq!(film.convert_frames(24, &video), 30);
+    assert_eq!(video.convert_frames(30, &film), 24);
+    ```
+    */
+    pub fn convert_frames(&self, frames: u64, to: &Timebase) -> u64 {
+        to.frames_from_milliseconds(self.milliseconds_from_frames(frames))
+    }
+
+    /**
+    生成时间范围内每一帧的时间码文本，用于叠加时间码。
+
+    范围和标准库一样包含开始点而不包含结束点，只生成落在范围之内的整帧。
+    因为 `core` 模块不依赖 `timeline` 模块，所以这里接受 `Range<Time>`，
+    对于时间线上的对象可以使用 `TimeRange::as_std_range()` 转换。
+    帧号和 `Time::to_total_frames()` 一样限制在 i64 的范围之内。
+    -----
+    Generate the timecode text of every frame within the range, for burn-ins.
+    The range includes the start but excludes the end.
+    Frame numbers are clamped to the i64 range like `Time::to_total_frames()`.
+    Use `TimeRange::as_std_range()` to pass a range from the timeline module.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    let timebase = Timebase::new(24);
+    let range = Time::from_millisecond(0)..Time::from_millisecond(500);
+    let timecodes: Vec<String> = timebase.timecodes_over(range).collect();
+    assert_eq!(
+        timecodes,
+        vec![
+            "00:00:00:00", "00:00:00:01", "00:00:00:02", "00:00:00:03",
+            "00:00:00:04", "00:00:00:05", "00:00:00:06", "00:00:00:07",
+            "00:00:00:08", "00:00:00:09", "00:00:00:10", "00:00:00:11",
+        ]
+    );
+    ```
+
+    ```rust
+    # use rusty_studio::core::{Time,Timebase};
+    # use rusty_studio::timeline::{Item, TimeRange};
+    let item = Item::from_std_range(Time::from_millisecond(1000)..Time::from_millisecond(1100));
+    let timecodes: Vec<String> = Timebase::new(30).timecodes_over(item.as_std_range()).collect();
+    assert_eq!(timecodes, vec!["00:00:01:00", "00:00:01:01", "00:00:01:02"]);
+
+    let timebase = Timebase::new(24);
+    let range = Time::from_millisecond(i128::MIN)..Time::ZERO;
+    let first = timebase.timecodes_over(range).next();
+    let expected = Time::from_total_frames(i64::MIN, &timebase).to_timecode(&timebase);
+    assert_eq!(first, Some(expected));
+    ```
+    */
+    pub fn timecodes_over(&self, range: Range<Time>) -> impl Iterator<Item = String> {
+        let timebase = *self;
+        let first_frame = |time: Time| -> i64 {
+            let frames = time.frames_rounded(&timebase, RoundMode::Ceil);
+            frames.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        };
+        let frames = if self.fps == 0 {
+            0..0
+        } else {
+            first_frame(range.start)..first_frame(range.end)
+        };
+        frames.map(move |frame| Time::from_total_frames(frame, &timebase).to_timecode(&timebase))
+    }
+
+    /**
+    判断两个时基是否属于同一个帧速率家族。
+
+    只比较帧速率而忽略丢帧信息，例如 24 丢帧（即 23.976）和 24 不丢帧属于同一家族，
+    但它们仍然是不相等的两个时基。
+    -----
+    Check whether two timebases belong to the same frame rate family,
+    which means they have the same fps regardless of drop frame.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Timebase;
+    let ntsc = Timebase{fps:24, drop_frame:true};
+    let film = Timebase{fps:24, drop_frame:false};
+    assert!(ntsc.same_family(&film));
+    assert_ne!(ntsc, film);
+
+    let pal = Timebase::new(25);
+    assert!(!film.same_family(&pal));
+    ```
+    */
+    pub fn same_family(&self, other: &Timebase) -> bool {
+        self.fps == other.fps
+    }
+
+    /**
+    列出某一分钟的第一秒中实际出现的帧号。
+
+    丢帧时间码会在每一分钟的开头跳过几个帧号，但是每逢十的整数倍分钟不跳过。
+    跳过的帧数按照常见的规范计算：29.97 跳过 2 帧，59.94 跳过 4 帧，
+    也就是只对 30 的倍数的帧速率有效，其它的帧速率即使丢帧也不会跳过任何帧号。
+    -----
+    List the frame numbers which actually occur in the first second of the given minute.
+
+    Drop frame timecode skips some frame numbers at the start of every minute,
+    except for every tenth minute.
+    2 frames are skipped for 29.97 and 4 frames for 59.94,
+    other frame rates don't skip any frame number.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::Timebase;
+    let timebase = Timebase::from_real_fps(29.97);
+    assert_eq!(timebase.valid_frame_numbers(0), (0..30).collect::<Vec<u32>>());
+    assert_eq!(timebase.valid_frame_numbers(1), (2..30).collect::<Vec<u32>>());
+    assert_eq!(timebase.valid_frame_numbers(10), (0..30).collect::<Vec<u32>>());
+
+    let timebase = Timebase::new(30);
+    assert_eq!(timebase.valid_frame_numbers(1), (0..30).collect::<Vec<u32>>());
+    ```
+    */
+    pub fn valid_frame_numbers(&self, minute: u64) -> Vec<u32> {
+        let dropped = if minute.is_multiple_of(10) {
+            0
+        } else {
+            self.dropped_per_minute()
+        };
+        (dropped..self.fps as u32).collect()
+    }
+
+    ///丢帧时间码在每一分钟开头跳过的帧号数量，每逢十的整数倍分钟除外。
+    fn dropped_per_minute(&self) -> u32 {
+        let fps = self.fps as u32;
+        if self.drop_frame && fps.is_multiple_of(30) {
+            fps / 15
+        } else {
+            0
+        }
+    }
+
+    ///把总帧数（按照整数帧速率计算的帧号）换算为跳过丢帧帧号之后的序号。
+    ///被跳过的帧号和它之后第一个实际出现的帧号得到相同的序号。
+    pub(crate) fn frame_ordinal(&self, frames: i128) -> i128 {
+        let dropped = self.dropped_per_minute() as i128;
+        if dropped == 0 {
+            return frames;
+        }
+        let per_minute = self.fps as i128 * 60;
+        let minutes = frames.div_euclid(per_minute);
+        let offset = frames.rem_euclid(per_minute);
+        let mut skipped = dropped * (minutes - minutes.div_euclid(10));
+        if minutes.rem_euclid(10) != 0 && offset < dropped {
+            skipped -= dropped - offset;
+        }
+        frames - skipped
+    }
+
+    ///`Timebase::frame_ordinal()` 的逆运算，把序号换算回总帧数。
+    pub(crate) fn frames_from_ordinal(&self, ordinal: i128) -> i128 {
+        let dropped = self.dropped_per_minute() as i128;
+        if dropped == 0 {
+            return ordinal;
+        }
+        let per_minute = self.fps as i128 * 60;
+        let per_ten_minutes = per_minute * 10 - dropped * 9;
+        let tens = ordinal.div_euclid(per_ten_minutes);
+        let rest = ordinal.rem_euclid(per_ten_minutes);
+        let extra = if rest < per_minute {
+            0
+        } else {
+            dropped * ((rest - per_minute) / (per_minute - dropped) + 1)
+        };
+        ordinal + dropped * 9 * tens + extra
+    }
 }
 
 impl Default for Timebase {
@@ -119,3 +319,98 @@ impl Default for Timebase {
         }
     }
 }
+
+/**
+从形如 “24p”、“29.97i”、“23.976” 的字符串解析时基信息。
+
+末尾的扫描方式标记（`p` 或 `i`）以及 `Display` 输出的丢帧标记 `DF` 会被忽略，
+剩余的数字交给 `Timebase::from_real_fps()` 识别。
+无法解析的字符串、小于 1 或过高的帧速率都会返回 `TimebaseFormatError` 错误。
+-----
+Parse timebase information from strings like "24p", "29.97i" or "23.976".
+
+A trailing scan flag (`p` or `i`) or the `DF` marker written by `Display` is ignored,
+the remaining number is recognized by `Timebase::from_real_fps()`.
+-----
+Example:
+```rust
+# use rusty_studio::core::Timebase;
+let timebase: Timebase = "23.976".parse().unwrap();
+assert_eq!(timebase.fps, 24);
+assert_eq!(timebase.drop_frame, true);
+
+let timebase: Timebase = "25p".parse().unwrap();
+assert_eq!(timebase.fps, 25);
+assert_eq!(timebase.drop_frame, false);
+
+let timebase: Timebase = "24.000p".parse().unwrap();
+assert_eq!(timebase, Timebase::new(24));
+
+assert!("something wrong".parse::<Timebase>().is_err());
+assert!("0p".parse::<Timebase>().is_err());
+```
+*/
+impl FromStr for Timebase {
+    type Err = TimebaseFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s
+            .strip_suffix("DF")
+            .or_else(|| s.strip_suffix("df"))
+            .unwrap_or(s)
+            .trim_end();
+        let s = s
+            .strip_suffix(['p', 'P', 'i', 'I'])
+            .unwrap_or(s)
+            .trim_end();
+        let fps: f64 = s.parse().map_err(|_| TimebaseFormatError)?;
+        if !fps.is_finite() || fps.round() < 1.0 || fps.round() > u8::MAX as f64 {
+            return Err(TimebaseFormatError);
+        }
+        Ok(Self::from_real_fps(fps))
+    }
+}
+
+/**
+把时基输出为便于阅读的文本，例如 “24p”、“25p”、“29.97 DF”。
+
+不丢帧的时基输出为整数帧速率加上 `p`；
+丢帧的时基输出实际的 1000/1001 帧速率（保留至多三位小数）并加上 `DF` 标记。
+帧速率不小于 1 时，输出的文本可以再通过 `str::parse()` 解析回相同的时基；
+帧速率为 0 的时基没有帧，输出为 `0p`，而 `FromStr` 会拒绝这个文本。
+-----
+Render the timebase as a human readable label, such as "24p", "25p" or "29.97 DF".
+Drop frame timebases render their real 1000/1001 rate with a `DF` marker.
+For fps 1 and above the label parses back to the same timebase;
+a timebase with fps 0 renders as `0p`, which `FromStr` rejects.
+-----
+Example:
+```rust
+# use rusty_studio::core::Timebase;
+assert_eq!(Timebase::new(24).to_string(), "24p");
+assert_eq!(Timebase::new(25).to_string(), "25p");
+assert_eq!(Timebase{fps:30, drop_frame:true}.to_string(), "29.97 DF");
+assert_eq!(Timebase{fps:24, drop_frame:true}.to_string(), "23.976 DF");
+
+for fps in 1..=u8::MAX {
+    for drop_frame in [false, true] {
+        let timebase = Timebase{fps, drop_frame};
+        assert_eq!(timebase.to_string().parse::<Timebase>(), Ok(timebase));
+    }
+}
+
+assert_eq!(Timebase::new(0).to_string(), "0p");
+assert!(Timebase::new(0).to_string().parse::<Timebase>().is_err());
+```
+*/
+impl std::fmt::Display for Timebase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.drop_frame {
+            return write!(f, "{}p", self.fps);
+        }
+        let real_fps = format!("{:.3}", self.fps as f64 * 1000.0 / 1001.0);
+        let real_fps = real_fps.trim_end_matches('0').trim_end_matches('.');
+        write!(f, "{} DF", real_fps)
+    }
+}
@@ -89,6 +89,7 @@ impl TimecodeParts {
 
     /**
     Parse timestamp parts from a String.
+    The separator before milliseconds can be `.`, `,`, `:` or `;`.
     
     Example:
     ```rust
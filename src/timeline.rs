@@ -5,3 +5,4 @@ mod traits;
 mod item;
 
 pub use traits::*;
+pub use item::*;
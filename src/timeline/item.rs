@@ -1,11 +1,13 @@
 #![allow(dead_code)]
 
-use crate::core::{DataBox, MetadataSupport, Time};
+use crate::core::{DataBox, HasDataBox, MetadataSupport, Time};
 use crate::timeline::{ContentSupport, TimeRange, TimeRangeEditable};
 use std::any::Any;
 use std::cell::{RefCell, RefMut};
 use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, Range};
 use std::rc::Rc;
+use std::sync::Arc;
 
 
 /**
@@ -25,6 +27,13 @@ pub struct Item {
 }
 
 impl Item {
+    ///保存倒放标记的元数据键。
+    pub const REVERSED_KEY: &'static str = "reversed";
+    ///保存源素材时长的元数据键。
+    pub const SOURCE_DURATION_KEY: &'static str = "source_duration";
+    ///保存源素材入点的元数据键。
+    pub const SOURCE_OFFSET_KEY: &'static str = "source_offset";
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -37,9 +46,511 @@ impl Item {
         }
     }
 
-    pub fn metadata(&self) -> RefMut<DataBox> {
+    /**
+    通过开始和结束时间点创建一个新的片段，时长为 `end - start`。
+
+    和 `TimeRangeEditable::set_end()` 一样，如果 end 早于 start，时长会变成负值，
+    可以用 `TimeRange::is_valid()` 检查。
+    -----
+    Construct an Item from its start and end, the duration is `end - start`.
+    Like `TimeRangeEditable::set_end()`, an `end` before `start` gives a negative duration,
+    which can be checked with `TimeRange::is_valid()`.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange};
+    let item = Item::from_endpoints(Time::from_millisecond(1000), Time::from_millisecond(2500));
+    assert_eq!(item.start().to_millisecond(), 1000);
+    assert_eq!(item.duration().to_millisecond(), 1500);
+    assert_eq!(item.end().to_millisecond(), 2500);
+    assert!(item.is_valid());
+
+    let item = Item::from_endpoints(Time::from_millisecond(2500), Time::from_millisecond(1000));
+    assert_eq!(item.duration().to_millisecond(), -1500);
+    assert_eq!(item.end().to_millisecond(), 1000);
+    assert!(!item.is_valid());
+    ```
+    */
+    pub fn from_endpoints(start: Time, end: Time) -> Self {
+        Self {
+            start,
+            duration: end - start,
+            ..Default::default()
+        }
+    }
+
+    ///从标准库的 `Range<Time>` 创建一个新的片段，相当于 `Item::from_endpoints()`。
+    pub fn from_std_range(range: Range<Time>) -> Self {
+        Self::from_endpoints(range.start, range.end)
+    }
+
+    pub fn metadata(&self) -> RefMut<'_, DataBox> {
         self.metadata.borrow_mut()
     }
+
+    /**
+    把片段等分为 n 个首尾相接的片段。
+
+    每个片段都是原片段的克隆，共享同一份内容。
+    时长无法整除时，余下的毫秒数会依次分配给前面的片段，所以所有片段恰好覆盖原来的时间段。
+    n 为 0 时返回空的列表。
+    -----
+    Split the item into `n` contiguous items which share the content.
+    The remainder milliseconds are distributed to the first pieces,
+    so the pieces exactly tile the original range.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange, TimeRangeEditable};
+    let mut item = Item::new();
+    item.set_start(Time::from_millisecond(500));
+    item.set_duration(Time::from_millisecond(1000));
+
+    let pieces = item.split_into(3);
+    let durations: Vec<i128> = pieces.iter().map(|p| p.duration().to_millisecond()).collect();
+    assert_eq!(durations, vec![334, 333, 333]);
+    assert_eq!(pieces[0].start(), item.start());
+    assert_eq!(pieces[1].start(), pieces[0].end());
+    assert_eq!(pieces[2].start(), pieces[1].end());
+    assert_eq!(pieces[2].end(), item.end());
+
+    assert!(item.split_into(0).is_empty());
+    ```
+    */
+    pub fn split_into(&self, n: usize) -> Vec<Item> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let total = self.duration.to_millisecond();
+        let base = total / n as i128;
+        let remainder = (total % n as i128).unsigned_abs() as usize;
+        let mut start = self.start;
+        (0..n)
+            .map(|i| {
+                let extra = if i < remainder { total.signum() } else { 0 };
+                let duration = Time::new(base + extra);
+                let mut piece = self.clone();
+                piece.set_start(start);
+                piece.set_duration(duration);
+                start += duration;
+                piece
+            })
+            .collect()
+    }
+
+    /**
+    按照倍数缩放片段的时长，同时保持锚点所在的时间不动。
+
+    Scale the duration of the item by `factor`, keeping the anchor time fixed.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Anchor, Item, TimeRange, TimeRangeEditable};
+    let mut item = Item::new();
+    item.set_start(Time::from_millisecond(1000));
+    item.set_duration(Time::from_millisecond(1000));
+
+    let mut centered = item.clone();
+    centered.scale_duration_about(2.0, Anchor::Center);
+    assert_eq!(centered.start().to_millisecond(), 500);
+    assert_eq!(centered.end().to_millisecond(), 2500);
+
+    let mut ended = item.clone();
+    ended.scale_duration_about(0.5, Anchor::End);
+    assert_eq!(ended.start().to_millisecond(), 1500);
+    assert_eq!(ended.end(), item.end());
+
+    let mut started = item.clone();
+    started.scale_duration_about(1.5, Anchor::Start);
+    assert_eq!(started.start(), item.start());
+    assert_eq!(started.end().to_millisecond(), 2500);
+    ```
+    */
+    pub fn scale_duration_about(&mut self, factor: f64, anchor: Anchor) {
+        let duration = self.duration * factor;
+        let start = match anchor {
+            Anchor::Start => self.start,
+            Anchor::Center => Time::midpoint(self.start, self.end()) - duration / 2.0,
+            Anchor::End => self.end() - duration,
+        };
+        self.start = start;
+        self.duration = duration;
+    }
+
+    /**
+    返回片段在 bounds 范围之内的可见部分。
+
+    结果是一个克隆的片段，其时间段为片段和 bounds 的交集，内容和元数据不变。
+    和 `TimeRange::overlaps()` 一样，首尾恰好相接时视为相交，结果的时长为 0；
+    完全在范围之外时返回 `None`。
+    -----
+    Return the visible portion of the item within `bounds`,
+    as a clone whose range is the intersection of the item and `bounds`.
+    Returns `None` if the item is fully outside.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange, TimeRangeEditable};
+    let range = |start, duration| {
+        let mut item = Item::new();
+        item.set_start(Time::from_millisecond(start));
+        item.set_duration(Time::from_millisecond(duration));
+        item
+    };
+    let bounds = range(1000, 1000);
+
+    let partly = range(500, 1000).clamped_to(&bounds).unwrap();
+    assert_eq!(partly.start().to_millisecond(), 1000);
+    assert_eq!(partly.end().to_millisecond(), 1500);
+
+    let inside = range(1200, 300).clamped_to(&bounds).unwrap();
+    assert_eq!(inside.start().to_millisecond(), 1200);
+    assert_eq!(inside.end().to_millisecond(), 1500);
+
+    assert!(range(3000, 500).clamped_to(&bounds).is_none());
+    ```
+    */
+    pub fn clamped_to(&self, bounds: &dyn TimeRange) -> Option<Item> {
+        let start = self.start().max(bounds.start());
+        let end = self.end().min(bounds.end());
+        if end < start {
+            return None;
+        }
+        let mut item = self.clone();
+        item.set_start(start);
+        item.set_end(end);
+        Some(item)
+    }
+
+    /**
+    设定片段所使用的源素材的总时长，`None` 表示没有限制。
+
+    片段的开始时间点对应源素材中 `source_offset()` 的位置，
+    所以片段实际使用的素材范围是从 `source_offset()` 到 `source_offset() + duration()`。
+    这两项信息保存在元数据中，克隆片段时会一并被复制。
+    -----
+    Set the total duration of the source media, `None` means unlimited.
+    The start of the item maps to `source_offset()` in the source media.
+    Both are stored in metadata.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange, TimeRangeEditable, TrimError};
+    let mut item = Item::new();
+    item.set_start(Time::from_millisecond(10_000));
+    item.set_duration(Time::from_millisecond(2_000));
+    item.set_source_duration(Some(Time::from_millisecond(5_000)));
+    item.set_source_offset(Time::from_millisecond(1_000));
+
+    assert!(item.try_trim_start(Time::from_millisecond(9_500)).is_ok());
+    assert_eq!(item.source_offset().to_millisecond(), 500);
+    assert_eq!(item.duration().to_millisecond(), 2_500);
+
+    assert_eq!(
+        item.try_trim_start(Time::from_millisecond(8_000)),
+        Err(TrimError::OutOfSource)
+    );
+    assert_eq!(item.start().to_millisecond(), 9_500);
+
+    assert!(item.try_trim_end(Time::from_millisecond(14_000)).is_ok());
+    assert_eq!(
+        item.try_trim_end(Time::from_millisecond(14_001)),
+        Err(TrimError::OutOfSource)
+    );
+    assert_eq!(
+        item.try_trim_end(Time::from_millisecond(9_000)),
+        Err(TrimError::NegativeDuration)
+    );
+    ```
+    */
+    pub fn set_source_duration(&mut self, duration: Option<Time>) {
+        match duration {
+            Some(duration) => self.set_metadata(Self::SOURCE_DURATION_KEY, duration),
+            None => self.erase_metadata(Self::SOURCE_DURATION_KEY),
+        }
+    }
+
+    ///源素材的总时长，没有设定时返回 `None`。
+    pub fn source_duration(&self) -> Option<Time> {
+        self.get_metadata::<Time>(Self::SOURCE_DURATION_KEY)
+    }
+
+    ///设定片段的开始时间点在源素材中对应的位置。
+    pub fn set_source_offset(&mut self, offset: Time) {
+        self.set_metadata(Self::SOURCE_OFFSET_KEY, offset);
+    }
+
+    ///片段的开始时间点在源素材中对应的位置，没有设定时为 0。
+    pub fn source_offset(&self) -> Time {
+        self.get_metadata::<Time>(Self::SOURCE_OFFSET_KEY).unwrap_or_default()
+    }
+
+    ///把开始时间点修剪到 start，结束时间点不变，源素材的入点随之移动。
+    ///如果超出源素材的开头或者时长会变成负值，不做任何修改并返回错误。
+    pub fn try_trim_start(&mut self, start: Time) -> Result<(), TrimError> {
+        let end = self.end();
+        if end < start {
+            return Err(TrimError::NegativeDuration);
+        }
+        let offset = self.source_offset() + (start - self.start);
+        if offset < Time::ZERO {
+            return Err(TrimError::OutOfSource);
+        }
+        if let Some(source_duration) = self.source_duration() {
+            if offset + (end - start) > source_duration {
+                return Err(TrimError::OutOfSource);
+            }
+        }
+        self.set_source_offset(offset);
+        self.set_start(start);
+        self.set_end(end);
+        Ok(())
+    }
+
+    ///把结束时间点修剪到 end，开始时间点不变。
+    ///如果超出源素材的结尾或者时长会变成负值，不做任何修改并返回错误。
+    pub fn try_trim_end(&mut self, end: Time) -> Result<(), TrimError> {
+        if end < self.start {
+            return Err(TrimError::NegativeDuration);
+        }
+        if let Some(source_duration) = self.source_duration() {
+            if self.source_offset() + (end - self.start) > source_duration {
+                return Err(TrimError::OutOfSource);
+            }
+        }
+        self.set_end(end);
+        Ok(())
+    }
+
+    /**
+    设定片段是否倒放。
+
+    倒放标记保存在元数据的 `Item::REVERSED_KEY` 键中，所以克隆片段时它也会一并被复制。
+    -----
+    Set whether the item is reversed.
+    The flag is stored in metadata under `Item::REVERSED_KEY`, so it is copied with clones.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRangeEditable};
+    let mut item = Item::new();
+    item.set_start(Time::from_millisecond(1000));
+    item.set_duration(Time::from_millisecond(500));
+    assert!(!item.is_reversed());
+
+    item.set_reversed(true);
+    let cloned = item.clone();
+    assert!(cloned.is_reversed());
+    assert_eq!(
+        cloned.effective_in_out(),
+        (Time::from_millisecond(1500), Time::from_millisecond(1000))
+    );
+    ```
+    */
+    pub fn set_reversed(&mut self, reversed: bool) {
+        self.set_metadata(Self::REVERSED_KEY, reversed);
+    }
+
+    ///片段是否倒放，没有设定时视为不倒放。
+    pub fn is_reversed(&self) -> bool {
+        self.get_metadata::<bool>(Self::REVERSED_KEY).unwrap_or(false)
+    }
+
+    ///返回实际的入点和出点。倒放时两者互换，即从结束时间点播放到开始时间点。
+    pub fn effective_in_out(&self) -> (Time, Time) {
+        if self.is_reversed() {
+            (self.end(), self.start())
+        } else {
+            (self.start(), self.end())
+        }
+    }
+
+    /**
+    以共享的 trait 对象的形式保存内容。
+
+    由于内容是以 `dyn Any` 保存的，一旦存入就无法再以 `&dyn Trait` 的形式取回。
+    此方法会把 `Arc<V>` 本身作为内容保存，之后可以通过 `get_content_as::<V>()` 取回同一个 trait 对象。
+
+    注意：以这种方式保存的内容只能以 *相同的* `V` 取回，
+    `get_content::<具体类型>()` 无法再取到原本的具体类型；
+    而且 `V` 必须满足 `Send + Sync`，所以 trait 对象需要写作 `dyn Trait + Send + Sync`。
+    -----
+    Store the content as a shared trait object.
+
+    Since content is stored as `dyn Any`, it can't be retrieved as `&dyn Trait` otherwise.
+    This method stores the `Arc<V>` itself as content,
+    so that `get_content_as::<V>()` returns the same trait object later.
+
+    Note: content stored this way can only be retrieved with the *same* `V`,
+    `get_content::<ConcreteType>()` won't see the concrete type any more.
+    `V` must be `Send + Sync`, so write trait objects as `dyn Trait + Send + Sync`.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::timeline::Item;
+    # use std::sync::Arc;
+    trait Renderable {
+        fn render(&self) -> String;
+    }
+
+    struct Title(String);
+    struct Marker;
+
+    impl Renderable for Title {
+        fn render(&self) -> String { format!("title: {}", self.0) }
+    }
+
+    impl Renderable for Marker {
+        fn render(&self) -> String { String::from("marker") }
+    }
+
+    type Shared = dyn Renderable + Send + Sync;
+
+    let mut title = Item::new();
+    title.set_content_as::<Shared>(Arc::new(Title(String::from("Hello"))));
+    let mut marker = Item::new();
+    marker.set_content_as::<Shared>(Arc::new(Marker));
+
+    let rendered: Vec<String> = [title, marker]
+        .iter()
+        .filter_map(|item| item.get_content_as::<Shared>())
+        .map(|content| content.render())
+        .collect();
+    assert_eq!(rendered, vec!["title: Hello", "marker"]);
+    ```
+    */
+    pub fn set_content_as<V>(&mut self, content: Arc<V>)
+    where
+        V: ?Sized + Send + Sync + 'static,
+    {
+        self.set_content(content)
+    }
+
+    /**
+    以共享指针的形式取回内容，不会复制内容本身。
+
+    `get_content::<T>()` 每次都会克隆内容，对于很大的内容来说代价较高；
+    而此方法返回的 `Rc<T>` 和 Item 共享同一份数据，类型不符时返回 `None`。
+    -----
+    Get the content as a shared pointer without cloning the content itself.
+    Returns `None` if the content is not of type `T`.
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::timeline::{Item, ContentSupport};
+    # use std::rc::Rc;
+    let mut item = Item::new();
+    item.set_content(vec![0u8; 1024]);
+
+    let buffer = item.content_rc::<Vec<u8>>().unwrap();
+    assert_eq!(buffer.len(), 1024);
+    assert_eq!(Rc::strong_count(&buffer), 2);
+
+    let another = item.content_rc::<Vec<u8>>().unwrap();
+    assert!(Rc::ptr_eq(&buffer, &another));
+    assert_eq!(Rc::strong_count(&buffer), 3);
+
+    assert!(item.content_rc::<String>().is_none());
+    ```
+    */
+    pub fn content_rc<T>(&self) -> Option<Rc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.content.clone().and_then(|c| {
+            let c: Rc<dyn Any> = c;
+            c.downcast::<T>().ok()
+        })
+    }
+
+    ///取回通过 `set_content_as::<V>()` 保存的 trait 对象，类型不符时返回 `None`。
+    pub fn get_content_as<V>(&self) -> Option<Arc<V>>
+    where
+        V: ?Sized + Send + Sync + 'static,
+    {
+        self.get_content::<Arc<V>>()
+    }
+
+    /**
+    克隆片段，并且深度复制其内容。
+
+    `Item::clone()` 只会共享同一份内容，而此方法会把内容按照类型 `T` 复制一份新的，
+    这样修改新片段的内容不会影响原来的片段。
+    由于内容是动态类型，如果内容不是 `T` 类型（或者没有内容），返回 `None`。
+    -----
+    Clone the item with a deep copy of its content of type `T`.
+    `Item::clone()` shares the content instead.
+    Returns `None` if the content is not of type `T` (or there is no content).
+    -----
+    Example:
+    ```rust
+    # use rusty_studio::timeline::{Item, ContentSupport};
+    # use std::rc::Rc;
+    let mut item = Item::new();
+    item.set_content(String::from("source"));
+
+    let shared = item.clone();
+    assert!(Rc::ptr_eq(
+        &item.content_rc::<String>().unwrap(),
+        &shared.content_rc::<String>().unwrap()
+    ));
+
+    let deep = item.clone_with_content::<String>().unwrap();
+    assert!(!Rc::ptr_eq(
+        &item.content_rc::<String>().unwrap(),
+        &deep.content_rc::<String>().unwrap()
+    ));
+    assert_eq!(deep.get_content::<String>(), Some(String::from("source")));
+
+    assert!(item.clone_with_content::<i32>().is_none());
+    ```
+    */
+    pub fn clone_with_content<T>(&self) -> Option<Item>
+    where
+        T: Any + Sync + Send + Clone,
+    {
+        let content = self.get_content::<T>()?;
+        let mut item = self.clone();
+        item.set_content(content);
+        Some(item)
+    }
+}
+
+///在修剪片段的操作无效时返回的错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrimError {
+    ///修剪后片段的时长会变成负值。
+    NegativeDuration,
+    ///修剪后片段会超出源素材的范围。
+    OutOfSource,
+}
+
+impl std::fmt::Display for TrimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TrimError::NegativeDuration => write!(f, "Negative Duration"),
+            TrimError::OutOfSource => write!(f, "Out Of Source Media"),
+        }
+    }
+}
+
+impl std::error::Error for TrimError {}
+
+///缩放片段时保持不动的锚点。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Anchor {
+    ///保持开始时间点不动。
+    Start,
+    ///保持中点不动。
+    Center,
+    ///保持结束时间点不动。
+    End,
 }
 
 impl Default for Item {
@@ -70,8 +581,8 @@ impl ContentSupport for Item {
         T: Any + Sync + Send + Clone,
     {
         self.content
-            .clone()
-            .and_then(|c| c.downcast_ref().and_then(Clone::clone))
+            .as_ref()
+            .and_then(|c| c.downcast_ref::<T>().cloned())
     }
 
     fn set_content<T>(&mut self, content: T)
@@ -106,21 +617,13 @@ impl TimeRangeEditable for Item {
     }
 }
 
-impl MetadataSupport for Item {
-    fn get_metadata<T: Any + Send + Sync + Clone>(&self, key: &String) -> Option<T> {
-        self.metadata.borrow().get(key)
-    }
-
-    fn set_metadata<T: Any + Send + Sync + Clone>(&mut self, key: &String, value: T) {
-        self.metadata.borrow_mut().set(key, value);
-    }
-
-    fn erase_metadata(&mut self, key: &String) {
-        self.metadata.borrow_mut().erase(key);
+impl HasDataBox for Item {
+    fn data_box(&self) -> impl Deref<Target = DataBox> + '_ {
+        self.metadata.borrow()
     }
 
-    fn clear_metadata(&mut self) {
-        self.metadata.borrow_mut().clear();
+    fn data_box_mut(&mut self) -> &mut DataBox {
+        self.metadata.get_mut()
     }
 }
 
@@ -14,6 +14,19 @@ pub trait ContentSupport {
 
 
 use crate::core::Time;
+use std::ops::Range;
+
+///在编辑时间段的操作会产生负的时长时返回的错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeDurationError;
+
+impl std::fmt::Display for NegativeDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Negative Duration")
+    }
+}
+
+impl std::error::Error for NegativeDurationError {}
 
 
 /**
@@ -35,6 +48,105 @@ pub trait TimeRange {
         self.start() + self.duration()
     }
 
+    /**
+    判断时间段是否有效，即时长不为负值。
+    编辑操作有可能产生负的时长，导出之前可以用它过滤掉无效的时间段。
+    -----
+    Check whether the duration is not negative.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange, TimeRangeEditable};
+    let mut item = Item::new();
+    assert!(item.is_valid());
+    assert!(item.is_empty());
+
+    item.set_duration(Time::from_millisecond(-500));
+    assert!(!item.is_valid());
+    assert!(!item.is_empty());
+    ```
+    */
+    fn is_valid(&self) -> bool {
+        self.duration() >= Time::ZERO
+    }
+
+    ///判断时间段的时长是否为 0。
+    fn is_empty(&self) -> bool {
+        self.duration() == Time::ZERO
+    }
+
+    /**
+    转换为标准库的 `Range<Time>`，即 `start()..end()`。
+
+    注意：标准库的范围不包含结束点，而 `TimeRange::contains()` 包含结束点。
+    -----
+    Convert to a std `Range<Time>`, which is `start()..end()`.
+    Note that std ranges exclude the end, while `TimeRange::contains()` includes it.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange};
+    let item = Item::from_std_range(Time::from_millisecond(1000)..Time::from_millisecond(2000));
+    assert_eq!(item.duration().to_millisecond(), 1000);
+
+    let range = item.as_std_range();
+    assert!(range.contains(&Time::from_millisecond(1500)));
+    assert!(!range.contains(&Time::from_millisecond(2000)));
+    assert!(item.contains(&Time::from_millisecond(2000)));
+    ```
+    */
+    fn as_std_range(&self) -> Range<Time> {
+        self.start()..self.end()
+    }
+
+    /**
+    把时间段从开始时间点起切分为若干个长度为 size 的首尾相接的范围，最后一段可能较短。
+    用于分窗分析或分块渲染。size 不大于 0 或者时间段的时长不大于 0 时不产生任何范围。
+    -----
+    Tile the time range into consecutive `Range<Time>` chunks of `size` from `start()`,
+    the last one may be shorter.
+    Nothing is yielded if `size` or the duration is not positive.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange};
+    let item = Item::from_std_range(Time::from_millisecond(0)..Time::from_millisecond(2500));
+    let lengths: Vec<i128> = item
+        .chunks(Time::from_millisecond(1000))
+        .map(|chunk| (chunk.end - chunk.start).to_millisecond())
+        .collect();
+    assert_eq!(lengths, vec![1000, 1000, 500]);
+
+    let last = item.chunks(Time::from_millisecond(1000)).last().unwrap();
+    assert_eq!(last, Time::from_millisecond(2000)..Time::from_millisecond(2500));
+
+    assert_eq!(item.chunks(Time::ZERO).count(), 0);
+    assert_eq!(item.chunks(Time::from_millisecond(-1000)).count(), 0);
+    ```
+    */
+    fn chunks(&self, size: Time) -> impl Iterator<Item = Range<Time>>
+    where
+        Self: Sized,
+    {
+        let start = self.start().to_millisecond();
+        let end = self.end().to_millisecond();
+        let size = size.to_millisecond();
+        let count = if size <= 0 || end <= start {
+            0
+        } else {
+            let length = end - start;
+            length / size + i128::from(length % size != 0)
+        };
+        (0..count).map(move |i| {
+            let chunk_start = start + i * size;
+            let chunk_end = chunk_start + (end - chunk_start).min(size);
+            Time::from_millisecond(chunk_start)..Time::from_millisecond(chunk_end)
+        })
+    }
+
     ///判断此时间段中是否包含某个时间点。
     fn contains(&self, time: &Time) -> bool {
         self.start() <= *time && *time <= self.end()
@@ -53,9 +165,39 @@ where
 {
     fn set_start(&mut self, start: Time);
     fn set_duration(&mut self, duration: Time);
+    ///通过修改时长来设定结束时间点。注意：如果 end 早于开始时间点，时长会变成负值。
     fn set_end(&mut self, end: Time) {
         self.set_duration(end - self.start());
     }
+
+    /**
+    通过修改时长来设定结束时间点，但是拒绝产生负的时长。
+    如果 end 早于开始时间点，不做任何修改并返回错误。
+    -----
+    Set the end by changing the duration, refusing to create a negative duration.
+
+    Example:
+    ```rust
+    # use rusty_studio::core::Time;
+    # use rusty_studio::timeline::{Item, TimeRange, TimeRangeEditable};
+    let mut item = Item::new();
+    item.set_start(Time::from_millisecond(1000));
+    item.set_duration(Time::from_millisecond(500));
+
+    assert!(item.try_set_end(Time::from_millisecond(2000)).is_ok());
+    assert_eq!(item.duration().to_millisecond(), 1000);
+
+    assert!(item.try_set_end(Time::from_millisecond(500)).is_err());
+    assert_eq!(item.end().to_millisecond(), 2000);
+    ```
+    */
+    fn try_set_end(&mut self, end: Time) -> Result<(), NegativeDurationError> {
+        if end < self.start() {
+            return Err(NegativeDurationError);
+        }
+        self.set_end(end);
+        Ok(())
+    }
     fn shift_time(&mut self, shift: Time) {
         self.set_start(self.start() + shift);
     }